@@ -9,6 +9,8 @@
 //! - Token refreshed automatically via node_secret auth when expired
 //! - **401 Recovery**: On HTTP 401, token is force-refreshed and request retried once
 //! - Tenant/workspace comes from token (EKKA decides scope)
+//! - **Completion outbox**: complete/fail results are persisted (encrypted) under
+//!   EKKA_HOME before sending and retried with backoff until delivered
 //!
 //! ## Security
 //!
@@ -20,10 +22,15 @@
 use crate::config;
 use crate::node_auth::{NodeSession, NodeSessionHolder, NodeSessionRunnerConfig};
 use crate::node_credentials::authenticate_node;
+use crate::node_vault_crypto::derive_runner_outbox_key;
+use crate::security_epoch::resolve_security_epoch;
 use crate::state::RunnerState;
+use ekka_runner_core::metrics::RunnerMetrics;
+use ekka_runner_core::pool::{ActiveTaskCounts, ConcurrencyConfig, WorkerPool};
 // Use ekka_runner_local for enhanced executor with debug bundle support
-use ekka_runner_local::dispatch::{classify_error, dispatch_task};
-use ekka_runner_local::types::{EngineContext, TaskExecutionContext};
+use ekka_runner_local::dispatch::{default_registry, DispatchContext, ExecutorRegistry, HeartbeatFn};
+use ekka_runner_local::outbox::{Completion, CompletionOutbox, DeliveryError, OutboxEntry};
+use ekka_runner_local::types::{CancelSignal, EngineContext, TaskExecutionContext};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -116,6 +123,14 @@ pub trait NodeRunnerCallback: Send + Sync {
     fn on_complete(&self, task_id: &str);
     fn on_error(&self, error: &str);
     fn on_stop(&self);
+    /// In-flight task counts changed
+    fn on_active_tasks(&self, _counts: &ActiveTaskCounts) {}
+    /// Number of undelivered completions changed
+    fn on_outbox_depth(&self, _depth: usize) {}
+    /// Metrics registry to record into (None = private registry)
+    fn metrics(&self) -> Option<Arc<RunnerMetrics>> {
+        None
+    }
 }
 
 /// Desktop callbacks that update RunnerState
@@ -158,6 +173,18 @@ impl NodeRunnerCallback for DesktopNodeRunnerCallbacks {
         info!(op = "node_runner.stop", "Node runner stopped");
         self.state.stop();
     }
+
+    fn on_active_tasks(&self, counts: &ActiveTaskCounts) {
+        self.state.record_active_tasks(counts);
+    }
+
+    fn on_outbox_depth(&self, depth: usize) {
+        self.state.record_outbox_depth(depth);
+    }
+
+    fn metrics(&self) -> Option<Arc<RunnerMetrics>> {
+        Some(self.state.metrics())
+    }
 }
 
 // =============================================================================
@@ -174,6 +201,12 @@ struct NodeSessionRunner {
     home_path: PathBuf,
     /// User subject (from JWT) for PathGuard grant validation
     user_sub: Option<String>,
+    /// Task executors keyed by capability_identity / subtype
+    executors: Arc<ExecutorRegistry<DispatchContext>>,
+    /// Durable completion outbox (None if it could not be opened - results are sent directly)
+    outbox: Option<CompletionOutbox>,
+    /// Poll/claim/task/heartbeat counters (shared with RunnerState)
+    metrics: Arc<RunnerMetrics>,
 }
 
 impl NodeSessionRunner {
@@ -182,6 +215,7 @@ impl NodeSessionRunner {
         session_holder: Arc<NodeSessionHolder>,
         home_path: PathBuf,
         user_sub: Option<String>,
+        metrics: Arc<RunnerMetrics>,
     ) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(60))
@@ -190,6 +224,21 @@ impl NodeSessionRunner {
 
         let runner_id = format!("{}-{}", RUNNER_ID_PREFIX, &Uuid::new_v4().to_string()[..8]);
 
+        let outbox = derive_runner_outbox_key(&home_path, resolve_security_epoch(&home_path))
+            .map_err(|e| e.to_string())
+            .and_then(|key| CompletionOutbox::open(&home_path, key));
+        let outbox = match outbox {
+            Ok(o) => Some(o),
+            Err(e) => {
+                warn!(
+                    op = "node_runner.outbox.unavailable",
+                    error = %e,
+                    "Completion outbox unavailable - results will not survive engine outages"
+                );
+                None
+            }
+        };
+
         Self {
             client,
             engine_url: config.engine_url.clone(),
@@ -199,6 +248,9 @@ impl NodeSessionRunner {
             session_holder,
             home_path,
             user_sub,
+            executors: Arc::new(default_registry()),
+            outbox,
+            metrics,
         }
     }
 
@@ -407,17 +459,13 @@ impl NodeSessionRunner {
 
     async fn complete_task(
         &self,
+        runner_id: &str,
         task_id: &str,
-        output: EngineCompleteOutput,
-        _duration_ms: Option<u64>,
-    ) -> Result<(), String> {
-        // Serialize output once for potential retry
-        let output_json = serde_json::to_value(&output)
-            .map_err(|e| format!("Failed to serialize output: {}", e))?;
-
+        output_json: &serde_json::Value,
+    ) -> Result<(), DeliveryError> {
         // Try up to 2 times (initial + 1 retry after 401)
         for attempt in 0..2 {
-            let session = self.get_session().await?;
+            let session = self.get_session().await.map_err(DeliveryError::transient)?;
 
             // V2 endpoint
             let url = format!(
@@ -425,7 +473,7 @@ impl NodeSessionRunner {
                 self.engine_url, task_id, session.tenant_id, session.workspace_id
             );
 
-            let headers = self.security_headers().await?;
+            let headers = self.security_headers().await.map_err(DeliveryError::transient)?;
             let mut req = self.client.post(&url);
             for (k, v) in headers {
                 req = req.header(k, v);
@@ -433,7 +481,7 @@ impl NodeSessionRunner {
             req = req.header("X-EKKA-ACTION", "complete");
 
             let body = EngineCompleteRequest {
-                runner_id: self.runner_id.clone(),
+                runner_id: runner_id.to_string(),
                 output: Some(output_json.clone()),
             };
 
@@ -450,7 +498,7 @@ impl NodeSessionRunner {
                 .json(&body)
                 .send()
                 .await
-                .map_err(|e| format!("Complete failed: {}", e))?;
+                .map_err(|e| DeliveryError::transient(format!("Complete failed: {}", e)))?;
 
             if response.status().is_success() {
                 return Ok(());
@@ -466,7 +514,7 @@ impl NodeSessionRunner {
                     task_id = %&task_id[..8.min(task_id.len())],
                     "Got 401 on complete, refreshing token and retrying"
                 );
-                self.force_refresh_session().await?;
+                self.force_refresh_session().await.map_err(DeliveryError::transient)?;
                 continue;
             }
 
@@ -476,26 +524,26 @@ impl NodeSessionRunner {
                 full_response = %body_text,
                 "Complete failed - FULL RESPONSE"
             );
-            return Err(format!(
-                "Complete failed ({}): {}",
-                status,
-                body_text
+            return Err(DeliveryError::from_status(
+                status.as_u16(),
+                format!("Complete failed ({}): {}", status, body_text),
             ));
         }
 
-        Err("Complete failed after retry".to_string())
+        Err(DeliveryError::transient("Complete failed after retry"))
     }
 
     async fn fail_task(
         &self,
+        runner_id: &str,
         task_id: &str,
         error: &str,
         code: &str,
         retryable: bool,
-    ) -> Result<(), String> {
+    ) -> Result<(), DeliveryError> {
         // Try up to 2 times (initial + 1 retry after 401)
         for attempt in 0..2 {
-            let session = self.get_session().await?;
+            let session = self.get_session().await.map_err(DeliveryError::transient)?;
 
             // V2 endpoint
             let url = format!(
@@ -503,7 +551,7 @@ impl NodeSessionRunner {
                 self.engine_url, task_id, session.tenant_id, session.workspace_id
             );
 
-            let headers = self.security_headers().await?;
+            let headers = self.security_headers().await.map_err(DeliveryError::transient)?;
             let mut req = self.client.post(&url);
             for (k, v) in headers {
                 req = req.header(k, v);
@@ -512,7 +560,7 @@ impl NodeSessionRunner {
 
             // V2 format: error_code required, error_message optional
             let body = EngineFailRequest {
-                runner_id: self.runner_id.clone(),
+                runner_id: runner_id.to_string(),
                 error_code: code.to_string(),
                 error_message: Some(error.to_string()),
                 retryable: Some(retryable),
@@ -522,7 +570,7 @@ impl NodeSessionRunner {
                 .json(&body)
                 .send()
                 .await
-                .map_err(|e| format!("Fail failed: {}", e))?;
+                .map_err(|e| DeliveryError::transient(format!("Fail failed: {}", e)))?;
 
             if response.status().is_success() {
                 return Ok(());
@@ -538,14 +586,111 @@ impl NodeSessionRunner {
                     task_id = %&task_id[..8.min(task_id.len())],
                     "Got 401 on fail, refreshing token and retrying"
                 );
-                self.force_refresh_session().await?;
+                self.force_refresh_session().await.map_err(DeliveryError::transient)?;
                 continue;
             }
 
-            return Err(format!("Fail failed ({})", status));
+            return Err(DeliveryError::from_status(status.as_u16(), format!("Fail failed ({})", status)));
         }
 
-        Err("Fail failed after retry".to_string())
+        Err(DeliveryError::transient("Fail failed after retry"))
+    }
+
+    /// Send one persisted completion (with the runner_id that claimed the task)
+    async fn send_completion(&self, entry: &OutboxEntry) -> Result<(), DeliveryError> {
+        match entry.completion {
+            Completion::Complete { ref output } => {
+                self.complete_task(&entry.runner_id, &entry.task_id, output).await
+            }
+            Completion::Fail {
+                ref error_code,
+                ref error_message,
+                retryable,
+            } => {
+                self.fail_task(&entry.runner_id, &entry.task_id, error_message, error_code, retryable)
+                    .await
+            }
+        }
+    }
+
+    /// Persist a completion to the outbox, then try to deliver it right away.
+    ///
+    /// On a transient failure the entry stays in the outbox and `flush_outbox` retries it.
+    async fn deliver(
+        &self,
+        task_id: &str,
+        nonce: &str,
+        completion: Completion,
+        cb: &Arc<dyn NodeRunnerCallback>,
+    ) -> Result<(), DeliveryError> {
+        let mut entry = OutboxEntry::new(task_id, nonce, &self.runner_id, completion);
+
+        let Some(ref outbox) = self.outbox else {
+            return self.send_completion(&entry).await;
+        };
+
+        // Keep a concurrent flush away from this entry until the inline send resolves
+        let Some(_guard) = outbox.begin_send(task_id, nonce) else {
+            info!(
+                op = "node_runner.outbox.already_sending",
+                task_id = %&task_id[..8.min(task_id.len())],
+                "Completion already being delivered"
+            );
+            return Ok(());
+        };
+
+        if let Err(e) = outbox.enqueue(&entry) {
+            warn!(
+                op = "node_runner.outbox.enqueue_failed",
+                task_id = %&task_id[..8.min(task_id.len())],
+                error = %e,
+                "Failed to persist completion - sending without outbox"
+            );
+        }
+        cb.on_outbox_depth(outbox.depth());
+
+        let result = self.send_completion(&entry).await;
+        match result {
+            Err(ref e) if !e.permanent => {
+                if let Err(write_err) = outbox.reschedule(&mut entry) {
+                    warn!(op = "node_runner.outbox.write_failed", error = %write_err, "Failed to reschedule completion");
+                }
+                warn!(
+                    op = "node_runner.outbox.queued",
+                    task_id = %&task_id[..8.min(task_id.len())],
+                    error = %e,
+                    "Completion delivery failed - queued for retry"
+                );
+            }
+            _ => {
+                if let Err(e) = outbox.remove(task_id, nonce) {
+                    warn!(op = "node_runner.outbox.remove_failed", error = %e, "Failed to remove delivered completion");
+                }
+            }
+        }
+        cb.on_outbox_depth(outbox.depth());
+        result
+    }
+
+    /// Retry due outbox entries (also run once on startup)
+    async fn flush_outbox(&self, cb: &Arc<dyn NodeRunnerCallback>) {
+        let Some(ref outbox) = self.outbox else {
+            return;
+        };
+
+        let stats = outbox
+            .flush(|entry| async move { self.send_completion(&entry).await })
+            .await;
+        if stats.delivered > 0 || stats.dropped > 0 {
+            info!(
+                op = "node_runner.outbox.flushed",
+                delivered = stats.delivered,
+                dropped = stats.dropped,
+                retrying = stats.retrying,
+                "Outbox flushed"
+            );
+        }
+        cb.on_outbox_depth(outbox.depth());
     }
 
 
@@ -576,6 +721,7 @@ impl NodeSessionRunner {
             }
         };
 
+        self.metrics.record_claim();
         cb.on_claim(task_id);
         info!(
             op = "node_runner.task.claimed",
@@ -583,6 +729,10 @@ impl NodeSessionRunner {
             "Task claimed"
         );
 
+        // Execution nonce: dedupes this execution's completion in the outbox; enqueueing it
+        // drops completions left by earlier executions of the same task
+        let nonce = Uuid::new_v4().to_string();
+
         // Build execution context
         let ctx = TaskExecutionContext::new(task_id.clone(), claim_result.input_json);
 
@@ -596,9 +746,12 @@ impl NodeSessionRunner {
                     error = %e,
                     "Failed to get session for execution"
                 );
-                let _ = self
-                    .fail_task(task_id, &e, "SESSION_ERROR", true)
-                    .await;
+                let completion = Completion::Fail {
+                    error_code: "SESSION_ERROR".to_string(),
+                    error_message: e.clone(),
+                    retryable: true,
+                };
+                let _ = self.deliver(task_id, &nonce, completion, cb).await;
                 cb.on_error(&e);
                 return;
             }
@@ -627,34 +780,42 @@ impl NodeSessionRunner {
             runner_id: self.runner_id.clone(),
             session_holder: self.session_holder.clone(),
             node_id: self.node_id,
+            cancel: ctx.cancel.clone(),
+            metrics: self.metrics.clone(),
         };
 
-        let heartbeat_fn: Arc<
-            dyn Fn()
-                    -> std::pin::Pin<
-                        Box<dyn std::future::Future<Output = Result<(), String>> + Send>,
-                    > + Send
-                + Sync,
-        > = Arc::new(move || {
+        let heartbeat_fn: HeartbeatFn = Arc::new(move || {
             let task_id = heartbeat_task_id.clone();
             let hb = heartbeat_self.clone();
 
             Box::pin(async move { hb.send_heartbeat(&task_id).await })
         });
 
-        // Dispatch to actual executor using mapped task_subtype
+        // Dispatch: exact capability_identity first, then the mapped task_subtype
         let start = std::time::Instant::now();
-        let result = dispatch_task(
-            task.task_subtype(),
-            &self.client,
-            &self.node_url,
-            "", // session_id not used for prompt_run
-            Some(&engine_ctx),
-            &ctx,
-            Some(heartbeat_fn),
-        )
-        .await;
-
+        let cx = DispatchContext {
+            client: self.client.clone(),
+            node_url: self.node_url.clone(),
+            session_id: String::new(), // session_id not used for prompt_run
+            engine_ctx: Some(engine_ctx),
+            task: ctx,
+            heartbeat_fn: Some(heartbeat_fn),
+        };
+        let result = self
+            .executors
+            .dispatch(
+                Some(&task.capability_identity),
+                task.task_subtype(),
+                task_id_short,
+                &cx,
+            )
+            .await;
+
+        self.metrics.record_task(
+            task.task_subtype().unwrap_or(&task.capability_identity),
+            start.elapsed(),
+            &result,
+        );
         let duration_ms = start.elapsed().as_millis() as u64;
 
         // Handle result
@@ -692,15 +853,24 @@ impl NodeSessionRunner {
                     reason,
                     proposed_patch: Some(vec![envelope]),
                 };
+                let output = match serde_json::to_value(&output) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        let e = format!("Failed to serialize output: {}", e);
+                        cb.on_error(&e);
+                        return;
+                    }
+                };
 
-                if let Err(e) = self.complete_task(task_id, output, Some(duration_ms)).await {
+                if let Err(e) = self.deliver(task_id, &nonce, Completion::Complete { output }, cb).await {
                     error!(
                         op = "node_runner.task.complete_failed",
                         task_id = %task_id_short,
                         error = %e,
+                        queued = !e.permanent && self.outbox.is_some(),
                         "Complete failed"
                     );
-                    cb.on_error(&e);
+                    cb.on_error(&e.message);
                 } else {
                     cb.on_complete(task_id);
                 }
@@ -709,22 +879,27 @@ impl NodeSessionRunner {
                 warn!(
                     op = "node_runner.task.failed",
                     task_id = %task_id_short,
-                    error = %e,
+                    code = %e.code(),
+                    error = %e.message,
                     duration_ms = %duration_ms,
                     "Task execution failed"
                 );
 
-                let (code, retryable) = classify_error(&e);
-
-                if let Err(fail_err) = self.fail_task(task_id, &e, code, retryable).await {
+                let completion = Completion::Fail {
+                    error_code: e.code().to_string(),
+                    error_message: e.message.clone(),
+                    retryable: e.retryable,
+                };
+                if let Err(fail_err) = self.deliver(task_id, &nonce, completion, cb).await {
                     error!(
                         op = "node_runner.task.fail_failed",
                         task_id = %task_id_short,
                         error = %fail_err,
+                        queued = !fail_err.permanent && self.outbox.is_some(),
                         "Fail request failed"
                     );
                 }
-                cb.on_error(&e);
+                cb.on_error(&e.to_string());
             }
         }
     }
@@ -742,10 +917,21 @@ struct NodeSessionRunnerHeartbeat {
     runner_id: String,
     session_holder: Arc<NodeSessionHolder>,
     node_id: Uuid, // Kept for headers only
+    /// Tripped when the heartbeat response reports the task was cancelled
+    cancel: CancelSignal,
+    metrics: Arc<RunnerMetrics>,
 }
 
 impl NodeSessionRunnerHeartbeat {
     async fn send_heartbeat(&self, task_id: &str) -> Result<(), String> {
+        let result = self.try_send_heartbeat(task_id).await;
+        if result.is_err() {
+            self.metrics.record_heartbeat_failure();
+        }
+        result
+    }
+
+    async fn try_send_heartbeat(&self, task_id: &str) -> Result<(), String> {
         // Get current session (refresh if needed)
         let session = if let Some(s) = self.session_holder.get_valid() {
             s
@@ -845,6 +1031,17 @@ impl NodeSessionRunnerHeartbeat {
             "Heartbeat succeeded"
         );
 
+        // Propagate cancellation to the executor (kills the in-flight LLM process)
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        if body.get("cancel_requested").and_then(|v| v.as_bool()) == Some(true) {
+            info!(
+                op = "prompt_run.heartbeat.cancel_requested",
+                task_id = %task_id_short,
+                "Task cancelled - aborting execution"
+            );
+            self.cancel.cancel();
+        }
+
         Ok(())
     }
 }
@@ -862,6 +1059,8 @@ const MAX_BACKOFF_SECS: u64 = 60;
 ///
 /// Uses node_id + node_secret auth for session refresh (NOT Ed25519).
 /// Includes backoff on repeated failures to prevent poll spam.
+/// Tasks run concurrently within the limits of `ConcurrencyConfig::from_env`;
+/// shutdown drains in-flight tasks up to its deadline.
 pub async fn run_node_session_runner_loop(
     config: NodeSessionRunnerConfig,
     session_holder: Arc<NodeSessionHolder>,
@@ -871,11 +1070,25 @@ pub async fn run_node_session_runner_loop(
     state_cb: Option<Arc<dyn NodeRunnerCallback>>,
     mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
 ) -> Result<(), String> {
-    let runner = NodeSessionRunner::new(&config, session_holder, home_path, user_sub);
     let cb = state_cb.unwrap_or_else(|| Arc::new(NoOpCallback));
+    let metrics = cb.metrics().unwrap_or_default();
+    let runner = Arc::new(NodeSessionRunner::new(&config, session_holder, home_path, user_sub, metrics));
+
+    let concurrency = ConcurrencyConfig::from_env().unwrap_or_else(|e| {
+        warn!(op = "node_runner.concurrency.invalid", error = %e, "Invalid concurrency config, using defaults");
+        ConcurrencyConfig::default()
+    });
+    let observer_cb = cb.clone();
+    let mut pool = WorkerPool::with_observer(
+        concurrency,
+        Some(Arc::new(move |counts: &ActiveTaskCounts| observer_cb.on_active_tasks(counts))),
+    );
 
     cb.on_start(&runner.runner_id);
 
+    // Deliver completions left over from a previous run (engine outage, crash)
+    runner.flush_outbox(&cb).await;
+
     info!(
         op = "node_runner.start",
         runner_id = %runner.runner_id,
@@ -892,14 +1105,30 @@ pub async fn run_node_session_runner_loop(
         // Check for shutdown signal
         if *shutdown_rx.borrow() {
             info!(op = "node_runner.shutdown", "Shutdown signal received");
-            cb.on_stop();
             break;
         }
 
+        // Retry undelivered completions whose backoff has elapsed
+        runner.flush_outbox(&cb).await;
+
+        // All slots busy: wait for a task to finish instead of polling
+        if pool.available() == 0 {
+            tokio::select! {
+                () = pool.wait_any() => {}
+                changed = shutdown_rx.changed() => {
+                    if changed.is_err() {
+                        break;
+                    }
+                }
+            }
+            continue;
+        }
+
         match runner.poll_tasks().await {
             Ok(tasks) => {
                 // Reset error count on success
                 consecutive_errors = 0;
+                runner.metrics.record_poll();
                 cb.on_poll();
 
                 if tasks.is_empty() {
@@ -909,7 +1138,6 @@ pub async fn run_node_session_runner_loop(
                         _ = shutdown_rx.changed() => {
                             if *shutdown_rx.borrow() {
                                 info!(op = "node_runner.shutdown", "Shutdown during poll wait");
-                                cb.on_stop();
                                 break;
                             }
                         }
@@ -923,14 +1151,39 @@ pub async fn run_node_session_runner_loop(
                     "Found pending tasks"
                 );
 
+                let mut started = 0;
                 for task in tasks {
-                    // Check shutdown before processing each task
+                    // Check shutdown before starting each task
                     if *shutdown_rx.borrow() {
                         info!(op = "node_runner.shutdown", "Shutdown before task processing");
-                        cb.on_stop();
-                        return Ok(());
+                        break;
                     }
-                    runner.process_task(&task, &cb).await;
+                    // Leave the task unclaimed if its capability has no free slot, and
+                    // skip tasks already in flight here (polled again before the claim landed)
+                    let Some(slot) = pool.try_acquire_task(&task.id, &task.capability_identity) else {
+                        continue;
+                    };
+                    started += 1;
+                    let runner = runner.clone();
+                    let cb = cb.clone();
+                    pool.spawn(slot, async move {
+                        runner.process_task(&task, &cb).await;
+                    });
+                }
+
+                // Nothing startable: wait for a slot to free up (or the next poll) instead of re-polling
+                if started == 0 {
+                    tokio::select! {
+                        () = pool.wait_any() => {}
+                        _ = tokio::time::sleep(Duration::from_secs(POLL_INTERVAL_SECS)) => {}
+                        _ = shutdown_rx.changed() => {
+                            if *shutdown_rx.borrow() {
+                                info!(op = "node_runner.shutdown", "Shutdown during slot wait");
+                                break;
+                            }
+                        }
+                    }
+                    continue;
                 }
             }
             Err(e) => {
@@ -963,6 +1216,10 @@ pub async fn run_node_session_runner_loop(
         tokio::time::sleep(Duration::from_secs(1)).await;
     }
 
+    // Graceful shutdown: let in-flight tasks finish up to the drain deadline
+    pool.drain().await;
+    cb.on_stop();
+
     Ok(())
 }
 
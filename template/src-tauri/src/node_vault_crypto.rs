@@ -10,6 +10,9 @@ use std::path::Path;
 /// Purpose label for node vault key derivation
 const NODE_VAULT_PURPOSE: &str = "node-vault";
 
+/// Purpose label for the runner completion outbox key
+const RUNNER_OUTBOX_PURPOSE: &str = "runner-outbox";
+
 /// Derive the encryption key for the node vault
 ///
 /// Key derivation inputs:
@@ -42,6 +45,23 @@ pub fn derive_node_vault_key(home: &Path, epoch: u32) -> anyhow::Result<KeyMater
     Ok(key)
 }
 
+/// Derive the encryption key for the runner completion outbox
+///
+/// Same device-bound inputs as the node vault key, separated by purpose label.
+/// Entries written under an older epoch become unreadable after rotation (and are quarantined).
+pub fn derive_runner_outbox_key(home: &Path, epoch: u32) -> anyhow::Result<KeyMaterial> {
+    let device_secret = load_or_create_device_secret(home)?;
+    let device_secret_hex = hex::encode(device_secret);
+
+    Ok(ekka_crypto::derive_key(
+        &device_secret_hex,
+        "runner-outbox-context",
+        epoch,
+        RUNNER_OUTBOX_PURPOSE,
+        &KeyDerivationConfig::default(),
+    ))
+}
+
 /// Decrypt ciphertext bytes using AES-256-GCM
 ///
 /// Expects the encrypted envelope format (version || nonce || ciphertext).
@@ -77,4 +97,14 @@ mod tests {
         assert_ne!(key1.as_bytes(), key2.as_bytes());
     }
 
+    #[test]
+    fn test_runner_outbox_key_separated_from_vault_key() {
+        let temp_dir = TempDir::new().unwrap();
+
+        let vault_key = derive_node_vault_key(temp_dir.path(), 1).unwrap();
+        let outbox_key = derive_runner_outbox_key(temp_dir.path(), 1).unwrap();
+
+        assert_ne!(vault_key.as_bytes(), outbox_key.as_bytes());
+    }
+
 }
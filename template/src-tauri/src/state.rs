@@ -6,6 +6,8 @@ use crate::core_process::CoreProcessManager;
 use crate::node_auth::NodeSessionHolder;
 use crate::node_credentials::NodeAuthTokenHolder;
 use chrono::{DateTime, Utc};
+use ekka_runner_core::metrics::{MetricsSnapshot, RunnerMetrics};
+use ekka_runner_core::pool::ActiveTaskCounts;
 use ekka_sdk_core::ekka_ops::{
     self as ops, EkkaError, EkkaResult, GrantIssuer, GrantRequest, GrantResponse, RuntimeContext,
     vault::{VaultCacheKey, VaultManager, VaultManagerCache},
@@ -13,7 +15,7 @@ use ekka_sdk_core::ekka_ops::{
 use ekka_sdk_core::ekka_path_guard::SignedGrant;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex, RwLock};
 use uuid::Uuid;
@@ -151,6 +153,15 @@ pub struct RunnerStatus {
     pub last_complete_at: Option<DateTime<Utc>>,
     pub last_task_id: Option<String>,
     pub last_error: Option<String>,
+    /// Tasks currently executing
+    pub active_tasks: usize,
+    /// Tasks currently executing per capability_identity
+    pub active_tasks_by_capability: BTreeMap<String, usize>,
+    /// Completions persisted but not yet delivered to the engine
+    pub outbox_depth: usize,
+    /// Counters from the runner metrics registry (filled in by `RunnerState::get`)
+    #[serde(skip_deserializing)]
+    pub metrics: MetricsSnapshot,
 }
 
 impl Default for RunnerStatus {
@@ -165,6 +176,10 @@ impl Default for RunnerStatus {
             last_complete_at: None,
             last_task_id: None,
             last_error: None,
+            active_tasks: 0,
+            active_tasks_by_capability: BTreeMap::new(),
+            outbox_depth: 0,
+            metrics: MetricsSnapshot::default(),
         }
     }
 }
@@ -173,21 +188,32 @@ impl Default for RunnerStatus {
 #[derive(Clone)]
 pub struct RunnerState {
     inner: Arc<RwLock<RunnerStatus>>,
+    /// Shared with the node runner loop, which records into it directly
+    metrics: Arc<RunnerMetrics>,
 }
 
 impl RunnerState {
     pub fn new() -> Self {
         Self {
             inner: Arc::new(RwLock::new(RunnerStatus::default())),
+            metrics: Arc::new(RunnerMetrics::new()),
         }
     }
 
     /// Get a snapshot of current runner status
     pub fn get(&self) -> RunnerStatus {
-        self.inner
+        let mut status = self
+            .inner
             .read()
             .map(|guard| guard.clone())
-            .unwrap_or_default()
+            .unwrap_or_default();
+        status.metrics = self.metrics.snapshot();
+        status
+    }
+
+    /// Metrics registry for the runner loop
+    pub fn metrics(&self) -> Arc<RunnerMetrics> {
+        self.metrics.clone()
     }
 
     /// Update runner status
@@ -224,6 +250,19 @@ impl RunnerState {
         });
     }
 
+    /// Update in-flight task counts
+    pub fn record_active_tasks(&self, counts: &ActiveTaskCounts) {
+        self.update(|s| {
+            s.active_tasks = counts.total;
+            s.active_tasks_by_capability = counts.by_capability.clone();
+        });
+    }
+
+    /// Update the number of undelivered completions
+    pub fn record_outbox_depth(&self, depth: usize) {
+        self.update(|s| s.outbox_depth = depth);
+    }
+
     /// Mark an error
     pub fn record_error(&self, error: &str) {
         self.update(|s| {
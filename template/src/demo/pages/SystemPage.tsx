@@ -522,6 +522,38 @@ export function SystemPage({ darkMode }: SystemPageProps): ReactElement {
                 : '—'}
             </span>
           </div>
+          <div style={styles.row}>
+            <span style={styles.label}>Active Tasks</span>
+            <span style={runnerStatus?.activeTasks ? styles.value : styles.valueMuted}>
+              {runnerStatus?.activeTasks
+                ? Object.entries(runnerStatus.activeTasksByCapability ?? {})
+                    .map(([capability, count]) => `${capability} × ${count}`)
+                    .join(' · ')
+                : '—'}
+            </span>
+          </div>
+          <div style={styles.row}>
+            <span style={styles.label}>Outbox</span>
+            <span style={runnerStatus?.outboxDepth ? styles.value : styles.valueMuted}>
+              {runnerStatus?.outboxDepth ? `${runnerStatus.outboxDepth} pending` : '—'}
+            </span>
+          </div>
+          <div style={styles.row}>
+            <span style={styles.label}>Tasks</span>
+            <span style={runnerStatus?.metrics?.claims ? styles.value : styles.valueMuted}>
+              {runnerStatus?.metrics?.claims
+                ? `${runnerStatus.metrics.claims} claimed · ${sumValues(runnerStatus.metrics.completions)} ok · ${sumValues(runnerStatus.metrics.failures)} failed`
+                : '—'}
+            </span>
+          </div>
+          <div style={styles.row}>
+            <span style={styles.label}>LLM Tokens</span>
+            <span style={runnerStatus?.metrics?.llmInputTokens ? styles.value : styles.valueMuted}>
+              {runnerStatus?.metrics?.llmInputTokens
+                ? `${runnerStatus.metrics.llmInputTokens} in · ${runnerStatus.metrics.llmOutputTokens} out`
+                : '—'}
+            </span>
+          </div>
           <div style={styles.rowLast}>
             <span style={styles.label}>Last Error</span>
             <span style={runnerStatus?.lastError ? { ...styles.value, color: colors.red } : styles.valueMuted}>
@@ -534,6 +566,10 @@ export function SystemPage({ darkMode }: SystemPageProps): ReactElement {
   );
 }
 
+function sumValues(counts: Record<string, number> | undefined): number {
+  return Object.values(counts ?? {}).reduce((total, n) => total + n, 0);
+}
+
 function formatHomeState(state: string | undefined): string {
   switch (state) {
     case 'HOME_GRANTED':
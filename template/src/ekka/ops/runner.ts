@@ -24,6 +24,30 @@ export interface RunnerStatus {
   lastCompleteAt: string | null;
   lastTaskId: string | null;
   lastError: string | null;
+  /** Tasks currently executing */
+  activeTasks: number;
+  /** Tasks currently executing per capability_identity */
+  activeTasksByCapability: Record<string, number>;
+  /** Completions persisted but not yet delivered to the engine */
+  outboxDepth: number;
+  /** Counters from the runner metrics registry */
+  metrics: RunnerMetrics;
+}
+
+/** Runner counters (same registry as the runner's Prometheus /metrics) */
+export interface RunnerMetrics {
+  polls: number;
+  claims: number;
+  /** Completions by task subtype */
+  completions: Record<string, number>;
+  /** Failures by failure code */
+  failures: Record<string, number>;
+  /** Task duration histograms by task subtype */
+  taskDurations: Record<string, { buckets: number[]; count: number; sumSecs: number }>;
+  llmInputTokens: number;
+  llmOutputTokens: number;
+  heartbeatFailures: number;
+  artifactBytes: number;
 }
 
 /** Task queue stats from engine API (V2) */